@@ -7,7 +7,16 @@ pub struct CpuStat {
     pub id: usize,
     pub window_size: usize,
     frequency_samples: VecDeque<u64>,
+    sample_indices: VecDeque<u64>,
+    next_index: u64,
     sum: u64,
+    sum_sq: u128,
+    // Monotonic deques of `(sample index, value)`, front holding the
+    // current window min/max. Kept in step with `frequency_samples` so an
+    // eviction from the front of the window can be mirrored here in O(1)
+    // amortized time instead of rescanning the window.
+    min_deque: VecDeque<(u64, u64)>,
+    max_deque: VecDeque<(u64, u64)>,
 }
 
 impl Display for CpuStat {
@@ -22,7 +31,12 @@ impl CpuStat {
             id,
             window_size,
             frequency_samples: VecDeque::with_capacity(window_size),
+            sample_indices: VecDeque::with_capacity(window_size),
+            next_index: 0,
             sum: 0,
+            sum_sq: 0,
+            min_deque: VecDeque::new(),
+            max_deque: VecDeque::new(),
         }
     }
 
@@ -34,13 +48,215 @@ impl CpuStat {
         self.sum as f64 / self.frequency_samples.len() as f64
     }
 
+    /// Population variance over the current window. Floating-point error
+    /// can push `sum_sq/n - mean^2` slightly negative for near-constant
+    /// samples; clamp that to zero.
+    pub fn variance(&self) -> f64 {
+        let n = self.frequency_samples.len() as f64;
+        if n == 0.0 {
+            return 0.0;
+        }
+        let mean = self.mean();
+        (self.sum_sq as f64 / n - mean * mean).max(0.0)
+    }
+
+    pub fn stddev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    pub fn min(&self) -> Option<u64> {
+        self.min_deque.front().map(|&(_, v)| v)
+    }
+
+    pub fn max(&self) -> Option<u64> {
+        self.max_deque.front().map(|&(_, v)| v)
+    }
+
+    /// The value at fractional position `p` (e.g. `0.95` for p95) in the
+    /// current window, computed by copying the window into a scratch
+    /// buffer and sorting it.
+    pub fn percentile(&self, p: f64) -> f64 {
+        if self.frequency_samples.is_empty() {
+            return 0.0;
+        }
+        let mut sorted: Vec<u64> = self.frequency_samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let last = sorted.len() - 1;
+        let index = ((last as f64) * p).round() as usize;
+        sorted[index.min(last)] as f64
+    }
+
     pub fn add_sample(&mut self, sample: u64) {
         if self.frequency_samples.len() == self.window_size {
             if let Some(v) = self.frequency_samples.pop_front() {
                 self.sum -= v;
+                self.sum_sq -= (v as u128) * (v as u128);
+            }
+            if let Some(evicted_index) = self.sample_indices.pop_front() {
+                if self.min_deque.front().is_some_and(|&(i, _)| i == evicted_index) {
+                    self.min_deque.pop_front();
+                }
+                if self.max_deque.front().is_some_and(|&(i, _)| i == evicted_index) {
+                    self.max_deque.pop_front();
+                }
             }
         }
+
+        let index = self.next_index;
+        self.next_index += 1;
         self.sum += sample;
+        self.sum_sq += (sample as u128) * (sample as u128);
         self.frequency_samples.push_back(sample);
+        self.sample_indices.push_back(index);
+
+        while self.min_deque.back().is_some_and(|&(_, v)| v >= sample) {
+            self.min_deque.pop_back();
+        }
+        self.min_deque.push_back((index, sample));
+        while self.max_deque.back().is_some_and(|&(_, v)| v <= sample) {
+            self.max_deque.pop_back();
+        }
+        self.max_deque.push_back((index, sample));
+    }
+}
+
+/// Sliding-window accumulator for CPU busy-fraction samples, mirroring
+/// `CpuStat`'s windowed mean but over `f64` utilization values in `[0.0, 1.0]`
+/// instead of `u64` frequencies.
+#[derive(Debug)]
+pub struct UsageStat {
+    pub id: usize,
+    pub window_size: usize,
+    usage_samples: VecDeque<f64>,
+    sum: f64,
+}
+
+impl Display for UsageStat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cpu {}: {:.1}%", self.id, self.avg_percent())
+    }
+}
+
+impl UsageStat {
+    pub fn new(id: usize, window_size: usize) -> Self {
+        Self {
+            id,
+            window_size,
+            usage_samples: VecDeque::with_capacity(window_size),
+            sum: 0.0,
+        }
+    }
+
+    pub fn avg_percent(&self) -> f64 {
+        self.mean() * 100.0
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.sum / self.usage_samples.len() as f64
+    }
+
+    pub fn add_sample(&mut self, sample: f64) {
+        if self.usage_samples.len() == self.window_size {
+            if let Some(v) = self.usage_samples.pop_front() {
+                self.sum -= v;
+            }
+        }
+        self.sum += sample;
+        self.usage_samples.push_back(sample);
+    }
+}
+
+/// Sliding-window accumulator for hwmon temperature samples (in
+/// millidegrees Celsius, matching the units `tempN_input` reports).
+#[derive(Debug)]
+pub struct TempStat {
+    pub label: String,
+    pub cpu_id: Option<usize>,
+    pub window_size: usize,
+    millicelsius_samples: VecDeque<i64>,
+    sum: i64,
+}
+
+impl Display for TempStat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {:.1}C", self.label, self.avg_celsius())
+    }
+}
+
+impl TempStat {
+    pub fn new(label: String, cpu_id: Option<usize>, window_size: usize) -> Self {
+        Self {
+            label,
+            cpu_id,
+            window_size,
+            millicelsius_samples: VecDeque::with_capacity(window_size),
+            sum: 0,
+        }
+    }
+
+    pub fn avg_celsius(&self) -> f64 {
+        self.sum as f64 / self.millicelsius_samples.len() as f64 / 1000.0
+    }
+
+    pub fn add_sample(&mut self, millicelsius: i64) {
+        if self.millicelsius_samples.len() == self.window_size {
+            if let Some(v) = self.millicelsius_samples.pop_front() {
+                self.sum -= v;
+            }
+        }
+        self.sum += millicelsius;
+        self.millicelsius_samples.push_back(millicelsius);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_max_evict_with_window() {
+        let mut stat = CpuStat::new(0, 3);
+        for sample in [5, 1, 4, 2] {
+            stat.add_sample(sample);
+        }
+        // Window is now [1, 4, 2]; the evicted `5` must not linger as min/max.
+        assert_eq!(stat.min(), Some(1));
+        assert_eq!(stat.max(), Some(4));
+
+        stat.add_sample(9);
+        // Window is now [4, 2, 9].
+        assert_eq!(stat.min(), Some(2));
+        assert_eq!(stat.max(), Some(9));
+    }
+
+    #[test]
+    fn variance_clamps_to_zero_for_constant_samples() {
+        let mut stat = CpuStat::new(0, 4);
+        for _ in 0..4 {
+            stat.add_sample(1_000_000);
+        }
+        assert_eq!(stat.variance(), 0.0);
+        assert_eq!(stat.stddev(), 0.0);
+    }
+
+    #[test]
+    fn percentile_boundaries() {
+        let mut stat = CpuStat::new(0, 5);
+        for sample in [10, 20, 30, 40, 50] {
+            stat.add_sample(sample);
+        }
+        assert_eq!(stat.percentile(0.0), 10.0);
+        assert_eq!(stat.percentile(0.5), 30.0);
+        assert_eq!(stat.percentile(1.0), 50.0);
+    }
+
+    #[test]
+    fn mean_tracks_window_after_eviction() {
+        let mut stat = CpuStat::new(0, 2);
+        stat.add_sample(10);
+        stat.add_sample(20);
+        stat.add_sample(30);
+        // Window is now [20, 30].
+        assert_eq!(stat.mean(), 25.0);
     }
 }
@@ -5,3 +5,21 @@ pub fn clear_screen() {
     print!("\x1B[2J\x1B[1;1H");
     stdout().flush().unwrap();
 }
+
+/// Glyphs used to render a normalized load value as a single Unicode block,
+/// from empty (index 0) to full (index 8).
+const BAR_GLYPHS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Map a load fraction in `[0.0, 1.0]` (values outside the range are clamped)
+/// onto one of the nine block glyphs in [`BAR_GLYPHS`].
+pub fn load_to_glyph(load: f64) -> char {
+    let load = load.clamp(0.0, 1.0);
+    let index = (8.0 * load) as usize;
+    BAR_GLYPHS[index.min(8)]
+}
+
+/// Render a sequence of per-core load fractions as a single dense line of
+/// bar glyphs, one character per core.
+pub fn render_bars(loads: impl IntoIterator<Item = f64>) -> String {
+    loads.into_iter().map(load_to_glyph).collect()
+}
@@ -1,15 +1,20 @@
 use std::io;
+use std::path::PathBuf;
 
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum ProbeError {
-    #[error("sysfs error: {0}")]
-    SysfsError(io::Error),
-    #[error("procfs error: {0}")]
-    ProcfsError(io::Error),
+    #[error("sysfs error reading {path}: {source}")]
+    SysfsError { path: PathBuf, source: io::Error },
+    #[error("procfs error reading {path}: {source}")]
+    ProcfsError { path: PathBuf, source: io::Error },
+    #[error("unexpected format in {path}: {reason}")]
+    FormatError { path: PathBuf, reason: String },
+    #[error("could not parse {value:?} from {path} as a number")]
+    ParseError { path: PathBuf, value: String },
     #[error("ID conversion error: {0} could not be converted to uint")]
     IntConversionError(String),
     #[error("invalid cpu ID: {0}")]
-    InvalidCpuId(usize),
+    InvalidCpuId(String),
 }
@@ -1,16 +1,19 @@
 use std::collections::{BTreeMap, HashSet};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::thread::sleep;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::Context;
 use clap::{Parser, Subcommand, ValueEnum, command};
+use serde::Serialize;
 
-use crate::cpustat::CpuStat;
-use crate::display::clear_screen;
-use crate::probe::{cpuset_with_stats, parse_procfs_cpuinfo, parse_sysfs_cpuinfo, probe_cpuset};
-use crate::probe::{read_sysfs_uint, validate_cpuset};
+use crate::cpustat::{CpuStat, TempStat, UsageStat};
+use crate::display::{clear_screen, render_bars};
+use crate::probe::{ProcfsCpuinfoReader, SysfsFreqReaders, probe_cpuset};
+use crate::probe::{CpuTimes, compute_usage, parse_procfs_stat, read_freq_bounds};
+use crate::probe::{TempSensor, discover_hwmon_temps, read_hwmon_temp, validate_cpuset};
 
 mod cpustat;
 mod display;
@@ -41,6 +44,17 @@ struct Cli {
     #[arg(global = true, long)]
     sample_freq: Option<u64>,
 
+    /// Which data to collect: `freq` for scaling frequency, `usage` for
+    /// `/proc/stat`-derived busy percentage, or `both`.
+    #[arg(global = true, value_enum, long)]
+    metric: Option<Metric>,
+
+    /// Also sample temperatures from every `/sys/class/hwmon/hwmon*/`
+    /// sensor, best-effort matched to a CPU ID when the chip exposes
+    /// `Core N`-style labels (e.g. `coretemp`, `k10temp`).
+    #[arg(global = true, long)]
+    with_temps: bool,
+
     /// The format data is output (monitor/file).
     #[command(subcommand)]
     output: Output,
@@ -53,6 +67,56 @@ enum Interface {
     Sysfs,
 }
 
+/// Which data the tool collects on each sample.
+#[derive(ValueEnum, Clone, Copy, Default, PartialEq, Eq)]
+enum Metric {
+    /// Scaling frequency only (the original behavior).
+    #[default]
+    Freq,
+    /// Busy percentage derived from `/proc/stat` jiffies only.
+    Usage,
+    /// Both frequency and usage.
+    Both,
+}
+
+impl Metric {
+    fn wants_freq(self) -> bool {
+        matches!(self, Metric::Freq | Metric::Both)
+    }
+
+    fn wants_usage(self) -> bool {
+        matches!(self, Metric::Usage | Metric::Both)
+    }
+}
+
+/// How the monitor subcommand renders each update.
+#[derive(ValueEnum, Clone, Default, PartialEq, Eq)]
+enum Style {
+    /// One `cpu N: X.XXXMHz` line per core.
+    #[default]
+    Text,
+    /// A single dense line of per-core bar glyphs, scaled between each
+    /// core's `scaling_min_freq` and `scaling_max_freq`.
+    Bars,
+}
+
+/// One core's reading within a [`JsonSample`].
+#[derive(Serialize)]
+struct JsonCpuSample {
+    freq_hz: Option<u64>,
+    avg_mhz: Option<f64>,
+    usage_pct: Option<f64>,
+}
+
+/// A single NDJSON line emitted by [`Output::Json`].
+#[derive(Serialize)]
+struct JsonSample {
+    t_ms: u128,
+    cpus: BTreeMap<usize, JsonCpuSample>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    temps: BTreeMap<String, f64>,
+}
+
 #[derive(Subcommand)]
 enum Output {
     Monitor {
@@ -65,12 +129,47 @@ enum Output {
         /// calculating CPU running average frequency.
         #[arg(long)]
         window_size: Option<usize>,
+
+        /// How to render each update: `text` for one line per core, or
+        /// `bars` for a single dense line of per-core bar glyphs.
+        #[arg(value_enum, long)]
+        style: Option<Style>,
     },
     Log {
         /// Destination CSV file to store CPU data.
         file: PathBuf,
         /// Duration in milliseconds to monitor for before exiting.
         duration_ms: u64,
+        /// How often (in milliseconds) to recompute the logged
+        /// `_mean`/`_min`/`_max`/`_stddev`/`_p95` columns from the running
+        /// window. The raw `cpuN` column is still written every sample
+        /// regardless of this cadence.
+        #[arg(long)]
+        update_freq: Option<u64>,
+        /// The number of data points to keep within a running total for
+        /// the logged `_mean`/`_min`/`_max`/`_stddev`/`_p95` frequency
+        /// columns.
+        #[arg(long)]
+        window_size: Option<usize>,
+    },
+    Json {
+        /// Destination file for the NDJSON stream. If left blank, writes to
+        /// stdout, making `freqprobe` pipeable into `jq` or a log shipper.
+        file: Option<PathBuf>,
+
+        /// The frequency which a JSON object is emitted with updated
+        /// running averages of `window-size` size.
+        #[arg(long)]
+        update_freq: Option<u64>,
+
+        /// The number of data points to keep within a running total for
+        /// calculating CPU running average frequency.
+        #[arg(long)]
+        window_size: Option<usize>,
+
+        /// Indent each JSON object instead of emitting it on a single line.
+        #[arg(long)]
+        pretty: bool,
     },
 }
 
@@ -79,6 +178,60 @@ impl Default for Output {
         Self::Monitor {
             update_freq: Some(DEFAULT_MONITOR_FREQUENCY),
             window_size: Some(DEFAULT_WINDOW_SIZE),
+            style: Some(Style::Text),
+        }
+    }
+}
+
+/// A per-interface frequency sampler, unifying the sysfs persistent-handle
+/// reader and the procfs `/proc/cpuinfo` reader behind one `sample_into`
+/// call so `monitor()` and `run_json()` don't each need their own
+/// `match self.interface` sampling step.
+enum FreqSampler {
+    Sysfs(SysfsFreqReaders),
+    Procfs {
+        reader: ProcfsCpuinfoReader,
+        scratch: BTreeMap<usize, u64>,
+    },
+}
+
+impl FreqSampler {
+    fn open(interface: Interface, cpuset: &HashSet<usize>) -> Self {
+        match interface {
+            Interface::Sysfs => Self::Sysfs(SysfsFreqReaders::open(cpuset)),
+            Interface::Procfs => Self::Procfs {
+                reader: ProcfsCpuinfoReader::open()
+                    .context("could not open /proc/cpuinfo")
+                    .unwrap(),
+                scratch: BTreeMap::new(),
+            },
+        }
+    }
+
+    fn sample_into(&mut self, cpuset: &HashSet<usize>, cpu_stats: &mut BTreeMap<usize, CpuStat>) {
+        match self {
+            Self::Sysfs(reader) => {
+                for (id, sample) in reader.sample() {
+                    match sample {
+                        Ok(sample) => {
+                            if let Some(window) = cpu_stats.get_mut(&id) {
+                                window.add_sample(sample * KILO);
+                            }
+                        }
+                        Err(err) => eprintln!("warning: skipping cpu {id}: {err}"),
+                    }
+                }
+            }
+            Self::Procfs { reader, scratch } => match reader.sample(cpuset, scratch) {
+                Ok(()) => {
+                    for (&id, &sample) in scratch.iter() {
+                        if let Some(window) = cpu_stats.get_mut(&id) {
+                            window.add_sample(sample * KILO);
+                        }
+                    }
+                }
+                Err(err) => eprintln!("warning: skipping frequency sample: {err}"),
+            },
         }
     }
 }
@@ -87,6 +240,8 @@ struct Runner {
     interface: Interface,
     cpuset: HashSet<usize>,
     sample_interval: Duration,
+    metric: Metric,
+    with_temps: bool,
     output: Output,
 }
 
@@ -95,99 +250,290 @@ impl Runner {
         interface: Interface,
         cpuset: HashSet<usize>,
         sample_frequency_ms: u64,
+        metric: Metric,
+        with_temps: bool,
         output: Output,
     ) -> Self {
         Self {
             interface,
             cpuset,
             sample_interval: Duration::from_millis(sample_frequency_ms),
+            metric,
+            with_temps,
             output,
         }
     }
 
+    /// Read `/proc/stat` and turn the delta against `prev` into a per-core
+    /// busy fraction, updating `prev` in place for the next call.
+    fn sample_usage(&self, prev: &mut Option<BTreeMap<usize, CpuTimes>>) -> BTreeMap<usize, f64> {
+        let curr = match parse_procfs_stat(&self.cpuset) {
+            Ok(curr) => curr,
+            Err(err) => {
+                eprintln!("warning: skipping usage sample: {err}");
+                return BTreeMap::new();
+            }
+        };
+        let usage = match prev {
+            Some(prev) => curr
+                .iter()
+                .map(|(&id, times)| {
+                    let usage = prev
+                        .get(&id)
+                        .map(|prev_times| compute_usage(prev_times, times))
+                        .unwrap_or(0.0);
+                    (id, usage)
+                })
+                .collect(),
+            None => curr.keys().map(|&id| (id, 0.0)).collect(),
+        };
+        *prev = Some(curr);
+        usage
+    }
+
+    fn sample_temps(&self, sensors: &[TempSensor], stats: &mut [TempStat]) {
+        for (sensor, stats) in sensors.iter().zip(stats.iter_mut()) {
+            match read_hwmon_temp(sensor) {
+                Ok(millicelsius) => stats.add_sample(millicelsius),
+                Err(err) => eprintln!("warning: skipping temp sensor {}: {err}", sensor.label),
+            }
+        }
+    }
+
+    /// One sampling tick shared by `monitor()` and `run_json()`: update
+    /// `cpu_stats`/`usage_stats`/`temp_stats` from whichever metrics are
+    /// enabled. `log()` has its own per-row variant since it also needs the
+    /// raw instantaneous values, not just the running windows.
+    fn sample_tick(
+        &self,
+        freq_sampler: &mut FreqSampler,
+        cpu_stats: &mut BTreeMap<usize, CpuStat>,
+        usage_stats: &mut BTreeMap<usize, UsageStat>,
+        prev_times: &mut Option<BTreeMap<usize, CpuTimes>>,
+        temp_sensors: &[TempSensor],
+        temp_stats: &mut [TempStat],
+    ) {
+        if self.metric.wants_freq() {
+            freq_sampler.sample_into(&self.cpuset, cpu_stats);
+        }
+        if self.metric.wants_usage() {
+            for (id, usage) in self.sample_usage(prev_times) {
+                if let Some(stats) = usage_stats.get_mut(&id) {
+                    stats.add_sample(usage);
+                }
+            }
+        }
+        if self.with_temps {
+            self.sample_temps(temp_sensors, temp_stats);
+        }
+    }
+
     fn run(&mut self) {
         match &self.output {
             Output::Monitor {
                 update_freq,
                 window_size,
+                style,
             } => self.monitor(
                 update_freq.unwrap_or(DEFAULT_MONITOR_FREQUENCY),
                 window_size.unwrap_or(DEFAULT_WINDOW_SIZE),
+                style.clone().unwrap_or_default(),
+            ),
+            Output::Log {
+                file,
+                duration_ms,
+                update_freq,
+                window_size,
+            } => self.log(
+                file.clone(),
+                *duration_ms,
+                update_freq.unwrap_or(DEFAULT_MONITOR_FREQUENCY),
+                window_size.unwrap_or(DEFAULT_WINDOW_SIZE),
+            ),
+            Output::Json {
+                file,
+                update_freq,
+                window_size,
+                pretty,
+            } => self.run_json(
+                file.clone(),
+                update_freq.unwrap_or(DEFAULT_MONITOR_FREQUENCY),
+                window_size.unwrap_or(DEFAULT_WINDOW_SIZE),
+                *pretty,
             ),
-            Output::Log { file, duration_ms } => self.log(file.clone(), *duration_ms),
         }
     }
-    fn monitor(&mut self, update_frequency_ms: u64, window_size: usize) {
+    fn monitor(&mut self, update_frequency_ms: u64, window_size: usize, style: Style) {
         let mut now = SystemTime::now();
         let update_interval = Duration::from_millis(update_frequency_ms);
         let mut next = now + update_interval;
-        match self.interface {
-            Interface::Sysfs => {
-                let mut cpu_files = parse_sysfs_cpuinfo(&self.cpuset)
-                    .context("could not parse sysfs CPU info")
-                    .unwrap();
-                let mut cpu_stats: BTreeMap<usize, CpuStat> = cpu_files
-                    .keys()
-                    .map(|&id| (id, CpuStat::new(id, window_size)))
-                    .collect();
-                loop {
-                    for (id, path) in &mut cpu_files {
-                        let sample = read_sysfs_uint(path) * KILO;
-                        if let Some(stats) = cpu_stats.get_mut(id) {
-                            stats.add_sample(sample);
-                        }
-                    }
+        let mut usage_stats: BTreeMap<usize, UsageStat> = if self.metric.wants_usage() {
+            self.cpuset
+                .iter()
+                .map(|&id| (id, UsageStat::new(id, window_size)))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+        let mut prev_times: Option<BTreeMap<usize, CpuTimes>> = None;
+        let temp_sensors = if self.with_temps {
+            discover_hwmon_temps()
+                .context("could not discover hwmon temperature sensors")
+                .unwrap()
+        } else {
+            Vec::new()
+        };
+        let mut temp_stats: Vec<TempStat> = temp_sensors
+            .iter()
+            .map(|sensor| TempStat::new(sensor.label.clone(), sensor.cpu_id, window_size))
+            .collect();
+        let mut cpu_stats: BTreeMap<usize, CpuStat> = self
+            .cpuset
+            .iter()
+            .map(|&id| (id, CpuStat::new(id, window_size)))
+            .collect();
+        let mut freq_sampler = FreqSampler::open(self.interface.clone(), &self.cpuset);
 
-                    now = SystemTime::now();
-                    if now > next {
-                        next = now + update_interval;
-                        clear_screen();
-                        for stats in cpu_stats.values() {
-                            println!("cpu {}: {:.3}MHz", stats.id, stats.avg_mhz())
-                        }
-                    }
-                    sleep(self.sample_interval);
-                }
-            }
-            Interface::Procfs => {
-                let mut cpu_stats = cpuset_with_stats(&self.cpuset)
-                    .context("could not parse cpuset")
-                    .unwrap();
-                loop {
-                    let cpu_frequencies = parse_procfs_cpuinfo(&self.cpuset)
-                        .context("could not parse sysfs CPU info")
-                        .unwrap();
-                    for (id, sample) in cpu_frequencies {
-                        if let Some(entry) = cpu_stats.get_mut(&id) {
-                            entry.add_sample(sample);
-                        }
-                    }
+        // `--style bars` normalizes against sysfs cpufreq scaling bounds, so
+        // it isn't meaningful on the procfs interface; fall back to text.
+        let style = if style == Style::Bars && !matches!(self.interface, Interface::Sysfs) {
+            eprintln!("--style bars requires the sysfs interface; falling back to text");
+            Style::Text
+        } else {
+            style
+        };
+        // Bars render a frequency gauge; with a metric that never samples
+        // frequency (e.g. `--metric usage`) there's nothing to normalize
+        // against, so the bars would render blank forever.
+        let style = if style == Style::Bars && !self.metric.wants_freq() {
+            eprintln!("--style bars requires a frequency metric; falling back to text");
+            Style::Text
+        } else {
+            style
+        };
+        let freq_bounds = if style == Style::Bars {
+            read_freq_bounds(&self.cpuset)
+                .context("could not read cpufreq scaling bounds")
+                .unwrap()
+        } else {
+            BTreeMap::new()
+        };
 
-                    now = SystemTime::now();
-                    if now > next {
-                        next = now + update_interval;
-                        clear_screen();
-                        for stats in cpu_stats.values() {
+        loop {
+            self.sample_tick(
+                &mut freq_sampler,
+                &mut cpu_stats,
+                &mut usage_stats,
+                &mut prev_times,
+                &temp_sensors,
+                &mut temp_stats,
+            );
+
+            now = SystemTime::now();
+            if now > next {
+                next = now + update_interval;
+                clear_screen();
+                match style {
+                    Style::Text => {
+                        for id in cpu_stats.keys().copied().collect::<Vec<_>>() {
+                            self.print_metric_line(id, &cpu_stats, &usage_stats, &temp_stats);
+                        }
+                        // Sensors that can't be tied to a specific core (no
+                        // `Core N`-style label) are reported on their own.
+                        for stats in temp_stats.iter().filter(|stats| stats.cpu_id.is_none()) {
                             println!("{stats}");
                         }
                     }
-                    sleep(self.sample_interval);
+                    Style::Bars => {
+                        let loads = cpu_stats.values().map(|stats| {
+                            let Some(bounds) = freq_bounds.get(&stats.id) else {
+                                return 0.0;
+                            };
+                            let min_hz = bounds.min_khz * KILO;
+                            let max_hz = bounds.max_khz * KILO;
+                            (stats.mean() - min_hz as f64) / (max_hz - min_hz) as f64
+                        });
+                        println!("{}", render_bars(loads));
+                    }
                 }
             }
+            sleep(self.sample_interval);
+        }
+    }
+
+    fn print_metric_line(
+        &self,
+        id: usize,
+        cpu_stats: &BTreeMap<usize, CpuStat>,
+        usage_stats: &BTreeMap<usize, UsageStat>,
+        temp_stats: &[TempStat],
+    ) {
+        if self.metric.wants_freq() {
+            if let Some(stats) = cpu_stats.get(&id) {
+                print!(
+                    "cpu {}: {:.3}MHz (min {:.3} max {:.3} stddev {:.3} p95 {:.3})",
+                    stats.id,
+                    stats.avg_mhz(),
+                    stats.min().unwrap_or(0) as f64 / 1_000_000.0,
+                    stats.max().unwrap_or(0) as f64 / 1_000_000.0,
+                    stats.stddev() / 1_000_000.0,
+                    stats.percentile(0.95) / 1_000_000.0,
+                );
+            }
         }
+        if self.metric.wants_usage() {
+            if let Some(stats) = usage_stats.get(&id) {
+                print!(" {:.1}%", stats.avg_percent());
+            }
+        }
+        // Pair this core's temp sensor(s) onto the same line so frequency,
+        // usage, and temperature can be watched together per core.
+        if self.with_temps {
+            for stats in temp_stats.iter().filter(|stats| stats.cpu_id == Some(id)) {
+                print!(" {:.1}C", stats.avg_celsius());
+            }
+        }
+        println!();
     }
 
-    fn get_log_header(cpuset: &HashSet<usize>) -> Vec<String> {
-        let mut header = Vec::with_capacity(cpuset.len());
-        header.extend({
-            let mut v: Vec<_> = cpuset.iter().collect();
-            v.sort();
-            v.into_iter().map(|id| format!("cpu{id}"))
-        });
+    fn get_log_header(cpuset: &HashSet<usize>, metric: Metric, temp_sensors: &[TempSensor]) -> Vec<String> {
+        let mut ids: Vec<_> = cpuset.iter().collect();
+        ids.sort();
+        let mut header = Vec::with_capacity(ids.len() * 6 + temp_sensors.len());
+        for id in ids {
+            if metric.wants_freq() {
+                header.push(format!("cpu{id}"));
+                header.push(format!("cpu{id}_mean"));
+                header.push(format!("cpu{id}_min"));
+                header.push(format!("cpu{id}_max"));
+                header.push(format!("cpu{id}_stddev"));
+                header.push(format!("cpu{id}_p95"));
+            }
+            if metric.wants_usage() {
+                header.push(format!("cpu{id}_usage"));
+            }
+        }
+        header.extend(
+            temp_sensors
+                .iter()
+                .map(|sensor| format!("temp_{}", sensor.label.replace(' ', "_"))),
+        );
         header
     }
 
-    fn log(&mut self, file: impl AsRef<Path>, duration_ms: u64) {
+    /// Render a `CpuStat` window's derived `_mean`/`_min`/`_max`/`_stddev`/
+    /// `_p95` columns (in kHz, matching the raw `cpuN` column) for a CSV row.
+    fn format_stat_cols(window: &CpuStat) -> [String; 5] {
+        [
+            window.mean().to_string(),
+            window.min().unwrap_or(0).to_string(),
+            window.max().unwrap_or(0).to_string(),
+            window.stddev().to_string(),
+            window.percentile(0.95).to_string(),
+        ]
+    }
+
+    fn log(&mut self, file: impl AsRef<Path>, duration_ms: u64, update_frequency_ms: u64, window_size: usize) {
         let file = fs::OpenOptions::new()
             .write(true)
             .create(true)
@@ -195,23 +541,102 @@ impl Runner {
             .open(file)
             .expect("unable to open provided file path for data logging.");
 
+        let temp_sensors = if self.with_temps {
+            discover_hwmon_temps()
+                .context("could not discover hwmon temperature sensors")
+                .unwrap()
+        } else {
+            Vec::new()
+        };
+
         use csv::Writer;
         let mut writer = Writer::from_writer(file);
-        let header = Self::get_log_header(&self.cpuset);
+        let header = Self::get_log_header(&self.cpuset, self.metric, &temp_sensors);
         writer
             .write_record(header)
             .expect("failed to write csv header");
         let end = SystemTime::now() + Duration::from_millis(duration_ms);
+        let update_interval = Duration::from_millis(update_frequency_ms);
+        let mut next = SystemTime::now() + update_interval;
+        let mut prev_times: Option<BTreeMap<usize, CpuTimes>> = None;
         match self.interface {
             Interface::Sysfs => {
-                let cpu_files = parse_sysfs_cpuinfo(&self.cpuset)
-                    .context("could not parse sysfs CPU info")
-                    .unwrap();
-                let mut record = Vec::with_capacity(cpu_files.len());
+                let mut freq_reader = SysfsFreqReaders::open(&self.cpuset);
+                let mut cpu_stats: BTreeMap<usize, CpuStat> = self
+                    .cpuset
+                    .iter()
+                    .map(|&id| (id, CpuStat::new(id, window_size)))
+                    .collect();
+                let mut ids: Vec<_> = self.cpuset.iter().copied().collect();
+                ids.sort();
+                let mut last_known: BTreeMap<usize, u64> = BTreeMap::new();
+                let mut stat_cols: BTreeMap<usize, [String; 5]> = BTreeMap::new();
+                let mut last_known_temps: Vec<f64> = vec![0.0; temp_sensors.len()];
+                let mut record = Vec::with_capacity(ids.len() * 6 + temp_sensors.len());
                 while SystemTime::now() < end {
-                    for path in &mut cpu_files.values() {
-                        let sample = read_sysfs_uint(path);
-                        record.push(sample.to_string());
+                    let usage = self
+                        .metric
+                        .wants_usage()
+                        .then(|| self.sample_usage(&mut prev_times));
+                    let freq_samples = self.metric.wants_freq().then(|| freq_reader.sample());
+                    let now = SystemTime::now();
+                    let due = now >= next;
+                    if due {
+                        next = now + update_interval;
+                    }
+                    for &id in &ids {
+                        if self.metric.wants_freq() {
+                            // Always emit the full set of freq columns, even
+                            // when this core has no entry in this tick's
+                            // `sample()` result -- either a transient read
+                            // failure, or a core `SysfsFreqReaders::open()`
+                            // dropped entirely at startup (e.g. offline) and
+                            // will never reappear -- so every row stays
+                            // aligned with the header.
+                            let sample = match freq_samples.as_ref().and_then(|s| s.get(&id)) {
+                                Some(Ok(sample)) => {
+                                    last_known.insert(id, *sample);
+                                    *sample
+                                }
+                                Some(Err(err)) => {
+                                    eprintln!("warning: skipping cpu {id}: {err}");
+                                    last_known.get(&id).copied().unwrap_or(0)
+                                }
+                                None => last_known.get(&id).copied().unwrap_or(0),
+                            };
+                            record.push(sample.to_string());
+                            if let Some(window) = cpu_stats.get_mut(&id) {
+                                window.add_sample(sample);
+                                // Only re-sort the window for the derived
+                                // columns at the update cadence; doing it
+                                // on every raw sample can't keep up with a
+                                // 1ms sample interval.
+                                if due {
+                                    stat_cols.insert(id, Self::format_stat_cols(window));
+                                }
+                                match stat_cols.get(&id) {
+                                    Some(cols) => record.extend(cols.iter().cloned()),
+                                    None => record.extend(vec!["0".to_string(); 5]),
+                                }
+                            }
+                        }
+                        if let Some(usage) = &usage {
+                            let value = usage.get(&id).copied().unwrap_or(0.0);
+                            record.push(value.to_string());
+                        }
+                    }
+                    for (sensor, last) in temp_sensors.iter().zip(last_known_temps.iter_mut()) {
+                        let celsius = match read_hwmon_temp(sensor) {
+                            Ok(millicelsius) => {
+                                *last = millicelsius as f64 / 1000.0;
+                                *last
+                            }
+                            Err(err) => {
+                                eprintln!("warning: skipping temp sensor {}: {err}", sensor.label);
+                                *last
+                            }
+                        };
+                        record.push(celsius.to_string());
                     }
                     writer
                         .write_record(&record)
@@ -221,18 +646,207 @@ impl Runner {
                 }
             }
             Interface::Procfs => {
+                let mut cpu_stats: BTreeMap<usize, CpuStat> = self
+                    .cpuset
+                    .iter()
+                    .map(|&id| (id, CpuStat::new(id, window_size)))
+                    .collect();
+                let mut cpuinfo_reader = ProcfsCpuinfoReader::open()
+                    .context("could not open /proc/cpuinfo")
+                    .unwrap();
+                let mut cpu_frequencies = BTreeMap::new();
+                let mut ids: Vec<_> = self.cpuset.iter().copied().collect();
+                ids.sort();
+                let mut last_known: BTreeMap<usize, u64> = BTreeMap::new();
+                let mut stat_cols: BTreeMap<usize, [String; 5]> = BTreeMap::new();
+                let mut last_known_temps: Vec<f64> = vec![0.0; temp_sensors.len()];
+                let mut record = Vec::with_capacity(ids.len() * 6 + temp_sensors.len());
                 while SystemTime::now() < end {
-                    let cpu_frequencies = parse_procfs_cpuinfo(&self.cpuset)
-                        .context("could not parse sysfs CPU info")
-                        .unwrap();
+                    let have_freqs = self.metric.wants_freq()
+                        && match cpuinfo_reader.sample(&self.cpuset, &mut cpu_frequencies) {
+                            Ok(()) => true,
+                            Err(err) => {
+                                eprintln!("warning: skipping frequency sample: {err}");
+                                false
+                            }
+                        };
+                    let usage = self
+                        .metric
+                        .wants_usage()
+                        .then(|| self.sample_usage(&mut prev_times));
+                    let now = SystemTime::now();
+                    let due = now >= next;
+                    if due {
+                        next = now + update_interval;
+                    }
+                    for &id in &ids {
+                        if self.metric.wants_freq() {
+                            // Always emit the full set of freq columns, even
+                            // when this tick's read failed or this core was
+                            // absent from /proc/cpuinfo (e.g. taken offline),
+                            // so every row stays aligned with the header.
+                            let freq = if have_freqs {
+                                match cpu_frequencies.get(&id) {
+                                    Some(&freq) => {
+                                        last_known.insert(id, freq);
+                                        freq
+                                    }
+                                    None => {
+                                        eprintln!(
+                                            "warning: cpu {id} missing from /proc/cpuinfo sample; reusing last known frequency"
+                                        );
+                                        last_known.get(&id).copied().unwrap_or(0)
+                                    }
+                                }
+                            } else {
+                                last_known.get(&id).copied().unwrap_or(0)
+                            };
+                            record.push(freq.to_string());
+                            if let Some(window) = cpu_stats.get_mut(&id) {
+                                window.add_sample(freq);
+                                // Only re-sort the window for the derived
+                                // columns at the update cadence; doing it
+                                // on every raw sample can't keep up with a
+                                // 1ms sample interval.
+                                if due {
+                                    stat_cols.insert(id, Self::format_stat_cols(window));
+                                }
+                                match stat_cols.get(&id) {
+                                    Some(cols) => record.extend(cols.iter().cloned()),
+                                    None => record.extend(vec!["0".to_string(); 5]),
+                                }
+                            }
+                        }
+                        if let Some(usage) = &usage {
+                            let value = usage.get(&id).copied().unwrap_or(0.0);
+                            record.push(value.to_string());
+                        }
+                    }
+                    for (sensor, last) in temp_sensors.iter().zip(last_known_temps.iter_mut()) {
+                        let celsius = match read_hwmon_temp(sensor) {
+                            Ok(millicelsius) => {
+                                *last = millicelsius as f64 / 1000.0;
+                                *last
+                            }
+                            Err(err) => {
+                                eprintln!("warning: skipping temp sensor {}: {err}", sensor.label);
+                                *last
+                            }
+                        };
+                        record.push(celsius.to_string());
+                    }
                     writer
-                        .write_record(cpu_frequencies.into_values().map(|v| v.to_string()))
+                        .write_record(&record)
                         .expect("failed to write csv record");
+                    record.clear();
                     sleep(self.sample_interval);
                 }
             }
         }
     }
+
+    fn run_json(&mut self, file: Option<PathBuf>, update_frequency_ms: u64, window_size: usize, pretty: bool) {
+        let mut out: Box<dyn Write> = match file {
+            Some(path) => Box::new(
+                fs::OpenOptions::new()
+                    .write(true)
+                    .create(true)
+                    .truncate(true)
+                    .open(path)
+                    .expect("unable to open provided file path for JSON logging."),
+            ),
+            None => Box::new(std::io::stdout()),
+        };
+
+        let update_interval = Duration::from_millis(update_frequency_ms);
+        let mut next = SystemTime::now() + update_interval;
+        let mut usage_stats: BTreeMap<usize, UsageStat> = if self.metric.wants_usage() {
+            self.cpuset
+                .iter()
+                .map(|&id| (id, UsageStat::new(id, window_size)))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+        let mut prev_times: Option<BTreeMap<usize, CpuTimes>> = None;
+        let temp_sensors = if self.with_temps {
+            discover_hwmon_temps()
+                .context("could not discover hwmon temperature sensors")
+                .unwrap()
+        } else {
+            Vec::new()
+        };
+        let mut temp_stats: Vec<TempStat> = temp_sensors
+            .iter()
+            .map(|sensor| TempStat::new(sensor.label.clone(), sensor.cpu_id, window_size))
+            .collect();
+
+        let mut cpu_stats: BTreeMap<usize, CpuStat> = self
+            .cpuset
+            .iter()
+            .map(|&id| (id, CpuStat::new(id, window_size)))
+            .collect();
+        let mut freq_sampler = FreqSampler::open(self.interface.clone(), &self.cpuset);
+
+        loop {
+            self.sample_tick(
+                &mut freq_sampler,
+                &mut cpu_stats,
+                &mut usage_stats,
+                &mut prev_times,
+                &temp_sensors,
+                &mut temp_stats,
+            );
+
+            let now = SystemTime::now();
+            if now > next {
+                next = now + update_interval;
+                let t_ms = now
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_millis())
+                    .unwrap_or(0);
+                let cpus = cpu_stats
+                    .keys()
+                    .map(|&id| {
+                        let freq_hz = self
+                            .metric
+                            .wants_freq()
+                            .then(|| cpu_stats.get(&id).unwrap().mean() as u64);
+                        let avg_mhz = self
+                            .metric
+                            .wants_freq()
+                            .then(|| cpu_stats.get(&id).unwrap().avg_mhz());
+                        let usage_pct = self
+                            .metric
+                            .wants_usage()
+                            .then(|| usage_stats.get(&id).unwrap().avg_percent());
+                        (
+                            id,
+                            JsonCpuSample {
+                                freq_hz,
+                                avg_mhz,
+                                usage_pct,
+                            },
+                        )
+                    })
+                    .collect();
+                let temps = temp_stats
+                    .iter()
+                    .map(|stats| (stats.label.clone(), stats.avg_celsius()))
+                    .collect();
+                let sample = JsonSample { t_ms, cpus, temps };
+                if pretty {
+                    serde_json::to_writer_pretty(&mut out, &sample)
+                        .expect("failed to write JSON sample");
+                } else {
+                    serde_json::to_writer(&mut out, &sample).expect("failed to write JSON sample");
+                }
+                out.write_all(b"\n").expect("failed to write JSON sample");
+                out.flush().expect("failed to flush JSON output");
+            }
+            sleep(self.sample_interval);
+        }
+    }
 }
 
 fn main() {
@@ -244,6 +858,14 @@ fn main() {
         .unwrap_or_else(probe_cpuset)
         .expect("couldn't determin cpuset");
     let sample_frequency_ms = args.sample_freq.unwrap_or(DEFAULT_SAMPLE_FREQUENCY);
-    let mut runner = Runner::new(interface, cpuset, sample_frequency_ms, args.output);
+    let metric = args.metric.unwrap_or_default();
+    let mut runner = Runner::new(
+        interface,
+        cpuset,
+        sample_frequency_ms,
+        metric,
+        args.with_temps,
+        args.output,
+    );
     runner.run();
 }
@@ -1,30 +1,40 @@
 use std::collections::{BTreeMap, HashSet};
 use std::fs;
-use std::io::{BufRead, Read};
+use std::io::{BufRead, Read, Seek, SeekFrom};
 use std::path::Path;
-use std::{fs::File, io::BufReader, path::PathBuf, process::exit};
+use std::{fs::File, io::BufReader, path::PathBuf};
 
 use crate::errors::ProbeError;
 
 const SYSFS_CPUS: &str = "/sys/devices/system/cpu";
 const SYSFS_CPUFREQ: &str = "/sys/devices/system/cpu/cpufreq";
 const PROCFS_CPUINFO: &str = "/proc/cpuinfo";
+const PROCFS_STAT: &str = "/proc/stat";
+const SYSFS_HWMON: &str = "/sys/class/hwmon";
 
-const WINDOW_SIZE: usize = 10000;
-
-use crate::cpustat::CpuStat;
-
-pub fn read_sysfs_uint(path: impl AsRef<Path>) -> u64 {
+pub fn read_sysfs_uint(path: impl AsRef<Path>) -> Result<u64, ProbeError> {
+    let path = path.as_ref();
     let mut s = String::new();
     let mut file = fs::OpenOptions::new()
         .read(true)
         .open(path)
-        .expect("couldn't open file");
-    let _ = file
-        .read_to_string(&mut s)
-        .expect("couldn't read from file");
-    let s = s.split_whitespace().take(1).next().unwrap();
-    s.parse::<u64>().unwrap()
+        .map_err(|source| ProbeError::SysfsError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    file.read_to_string(&mut s)
+        .map_err(|source| ProbeError::SysfsError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    let token = s.split_whitespace().next().ok_or_else(|| ProbeError::FormatError {
+        path: path.to_path_buf(),
+        reason: "file was empty".to_string(),
+    })?;
+    token.parse::<u64>().map_err(|_| ProbeError::ParseError {
+        path: path.to_path_buf(),
+        value: token.to_string(),
+    })
 }
 
 pub fn validate_cpuset(cpuset: String) -> Result<HashSet<usize>, ProbeError> {
@@ -66,59 +76,345 @@ fn sysfs_cpu_path(id: usize) -> PathBuf {
         .join("scaling_cur_freq")
 }
 
-pub fn cpuset_with_stats(cpuset: &HashSet<usize>) -> Result<BTreeMap<usize, CpuStat>, ProbeError> {
-    let cpu_files: BTreeMap<_, _> = cpuset
-        .into_iter()
-        .map(|&id| (id, CpuStat::new(id, WINDOW_SIZE)))
-        .collect();
-    Ok(cpu_files)
+fn sysfs_cpufreq_bound_path(id: usize, file: &str) -> PathBuf {
+    Path::new(SYSFS_CPUS)
+        .join(format!("cpu{id}"))
+        .join("cpufreq")
+        .join(file)
 }
 
-pub fn parse_sysfs_cpuinfo(
-    cpuset: &HashSet<usize>,
-) -> Result<BTreeMap<PathBuf, CpuStat>, ProbeError> {
-    let cpu_files: BTreeMap<_, _> = cpuset
-        .into_iter()
-        .map(|&id| (sysfs_cpu_path(id), CpuStat::new(id, WINDOW_SIZE)))
-        .collect();
-    Ok(cpu_files)
+/// The minimum and maximum scaling frequency (in kHz, as reported by sysfs)
+/// a core can run at, used to normalize a live reading into a `[0.0, 1.0]`
+/// load fraction.
+#[derive(Debug, Clone, Copy)]
+pub struct FreqBounds {
+    pub min_khz: u64,
+    pub max_khz: u64,
 }
 
-/// parse /proc/cpuinfo to get every CPU's current frequency
-pub fn parse_procfs_cpuinfo(cpuset: &HashSet<usize>) -> Result<BTreeMap<usize, u64>, ProbeError> {
-    let mut cpu_frequencies = BTreeMap::new();
-    let file = File::open(PROCFS_CPUINFO).expect("couldn't open procfs file");
+/// Read `scaling_min_freq`/`scaling_max_freq` for every CPU in `cpuset` once.
+/// Intended to be called at startup, since these bounds don't change while
+/// the tool is running.
+pub fn read_freq_bounds(cpuset: &HashSet<usize>) -> Result<BTreeMap<usize, FreqBounds>, ProbeError> {
+    let mut bounds = BTreeMap::new();
+    for &id in cpuset {
+        let min_khz = read_sysfs_uint(sysfs_cpufreq_bound_path(id, "scaling_min_freq"));
+        let max_khz = read_sysfs_uint(sysfs_cpufreq_bound_path(id, "scaling_max_freq"));
+        match (min_khz, max_khz) {
+            (Ok(min_khz), Ok(max_khz)) => {
+                bounds.insert(id, FreqBounds { min_khz, max_khz });
+            }
+            (Err(err), _) | (_, Err(err)) => {
+                eprintln!("warning: skipping cpu {id} scaling bounds: {err}");
+            }
+        }
+    }
+    Ok(bounds)
+}
+
+/// Persistent handles onto every CPU's `scaling_cur_freq` node, opened once
+/// and re-read in place on every sample (`seek`ing back to the start of the
+/// file) instead of reopening the node each tick.
+pub struct SysfsFreqReaders {
+    handles: BTreeMap<usize, (PathBuf, File)>,
+    buf: String,
+}
+
+impl SysfsFreqReaders {
+    /// Open a handle for every CPU in `cpuset`. A core whose node can't be
+    /// opened (e.g. it went offline between discovery and startup) is
+    /// skipped with a warning rather than failing the whole tool.
+    pub fn open(cpuset: &HashSet<usize>) -> Self {
+        let mut handles = BTreeMap::new();
+        for &id in cpuset {
+            let path = sysfs_cpu_path(id);
+            match fs::OpenOptions::new().read(true).open(&path) {
+                Ok(file) => {
+                    handles.insert(id, (path, file));
+                }
+                Err(source) => {
+                    eprintln!(
+                        "warning: skipping cpu {id}: {}",
+                        ProbeError::SysfsError { path, source }
+                    );
+                }
+            }
+        }
+        Self {
+            handles,
+            buf: String::new(),
+        }
+    }
+
+    /// Re-read every open handle's current value, reusing the same scratch
+    /// buffer for every core instead of allocating one per sample.
+    pub fn sample(&mut self) -> BTreeMap<usize, Result<u64, ProbeError>> {
+        let Self { handles, buf } = self;
+        handles
+            .iter_mut()
+            .map(|(&id, (path, file))| (id, Self::read_one(path, file, buf)))
+            .collect()
+    }
+
+    fn read_one(path: &Path, file: &mut File, buf: &mut String) -> Result<u64, ProbeError> {
+        buf.clear();
+        file.seek(SeekFrom::Start(0))
+            .map_err(|source| ProbeError::SysfsError {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        file.read_to_string(buf)
+            .map_err(|source| ProbeError::SysfsError {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        let token = buf.split_whitespace().next().ok_or_else(|| ProbeError::FormatError {
+            path: path.to_path_buf(),
+            reason: "file was empty".to_string(),
+        })?;
+        token.parse::<u64>().map_err(|_| ProbeError::ParseError {
+            path: path.to_path_buf(),
+            value: token.to_string(),
+        })
+    }
+}
+
+/// A persistent reader over `/proc/cpuinfo`, reusing one open file handle
+/// and read buffer across samples instead of reopening the file and
+/// allocating a new buffer on every tick. The whole file is still read and
+/// parsed on each sample.
+pub struct ProcfsCpuinfoReader {
+    file: File,
+    buf: String,
+}
+
+impl ProcfsCpuinfoReader {
+    pub fn open() -> Result<Self, ProbeError> {
+        let path = Path::new(PROCFS_CPUINFO);
+        let file = File::open(path).map_err(|source| ProbeError::ProcfsError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Ok(Self {
+            file,
+            buf: String::new(),
+        })
+    }
+
+    /// Re-read `/proc/cpuinfo` from the start and write every CPU in
+    /// `cpuset`'s current frequency into `out`, which is cleared first but
+    /// otherwise reused across calls to avoid reallocating on every sample.
+    pub fn sample(
+        &mut self,
+        cpuset: &HashSet<usize>,
+        out: &mut BTreeMap<usize, u64>,
+    ) -> Result<(), ProbeError> {
+        out.clear();
+        self.buf.clear();
+        let path = Path::new(PROCFS_CPUINFO);
+        self.file
+            .seek(SeekFrom::Start(0))
+            .map_err(|source| ProbeError::ProcfsError {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        self.file
+            .read_to_string(&mut self.buf)
+            .map_err(|source| ProbeError::ProcfsError {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        let mut current = None;
+        for line in self.buf.lines() {
+            if let Some(id) = current {
+                let Some(line) = line.strip_prefix("cpu MHz") else {
+                    continue;
+                };
+                let line = line.trim_start();
+                let Some(line) = line.strip_prefix(":") else {
+                    eprintln!("warning: skipping malformed cpu MHz line in {PROCFS_CPUINFO}");
+                    current = None;
+                    continue;
+                };
+                let line = line.trim_start();
+                let Ok(frequency_mhz) = line.parse::<f64>() else {
+                    eprintln!("warning: could not parse frequency {line:?} in {PROCFS_CPUINFO}");
+                    current = None;
+                    continue;
+                };
+                out.insert(id, (frequency_mhz * 1000.0) as u64);
+                current = None;
+            } else {
+                let Some(line) = line.strip_prefix("processor") else {
+                    continue;
+                };
+                let line = line.trim_start();
+                let Some(line) = line.strip_prefix(":") else {
+                    eprintln!("warning: skipping malformed processor line in {PROCFS_CPUINFO}");
+                    continue;
+                };
+                let line = line.trim_start();
+                let Ok(id) = line.parse::<usize>() else {
+                    eprintln!("warning: could not parse cpu id {line:?} in {PROCFS_CPUINFO}");
+                    continue;
+                };
+                if cpuset.contains(&id) {
+                    current = Some(id);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Jiffie counters for a single core, as reported by one `cpuN` line of
+/// `/proc/stat`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpuTimes {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+}
+
+impl CpuTimes {
+    fn total(&self) -> u64 {
+        self.user
+            + self.nice
+            + self.system
+            + self.idle
+            + self.iowait
+            + self.irq
+            + self.softirq
+            + self.steal
+    }
+
+    fn idle_total(&self) -> u64 {
+        self.idle + self.iowait
+    }
+}
+
+/// Parse the per-core `cpuN user nice system idle iowait irq softirq steal`
+/// lines out of `/proc/stat` for every CPU in `cpuset`.
+pub fn parse_procfs_stat(cpuset: &HashSet<usize>) -> Result<BTreeMap<usize, CpuTimes>, ProbeError> {
+    let path = Path::new(PROCFS_STAT);
+    let mut cpu_times = BTreeMap::new();
+    let file = File::open(path).map_err(|source| ProbeError::ProcfsError {
+        path: path.to_path_buf(),
+        source,
+    })?;
     let reader = BufReader::new(file);
-    let mut current = None;
     for line in reader.lines().map_while(Result::ok) {
-        if let Some(id) = current {
-            let Some(line) = line.strip_prefix("cpu MHz") else {
-                continue;
-            };
-            let line = line.trim_start();
-            let Some(line) = line.strip_prefix(":") else {
-                eprintln!("incorrrect file format");
-                exit(1)
-            };
-            let line = line.trim_start();
-            let frequency_mhz = line.parse::<f64>().expect("couldn't parse frequency");
-            cpu_frequencies.insert(id, (frequency_mhz * 1000.0) as u64);
-            current = None;
-        } else {
-            let Some(line) = line.strip_prefix("processor") else {
+        let Some(rest) = line.strip_prefix("cpu") else {
+            continue;
+        };
+        let mut fields = rest.split_whitespace();
+        let Some(id) = fields
+            .next()
+            .filter(|_| !rest.starts_with(char::is_whitespace))
+            .and_then(|id| id.parse::<usize>().ok())
+        else {
+            // the aggregate "cpu " line has no ID; skip it
+            continue;
+        };
+        if !cpuset.contains(&id) {
+            continue;
+        }
+        let mut jiffies = [0u64; 8];
+        for slot in &mut jiffies {
+            *slot = fields.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+        }
+        cpu_times.insert(
+            id,
+            CpuTimes {
+                user: jiffies[0],
+                nice: jiffies[1],
+                system: jiffies[2],
+                idle: jiffies[3],
+                iowait: jiffies[4],
+                irq: jiffies[5],
+                softirq: jiffies[6],
+                steal: jiffies[7],
+            },
+        );
+    }
+    Ok(cpu_times)
+}
+
+/// Compute the busy fraction between two `/proc/stat` snapshots of the same
+/// core. Returns `0.0` if no time has passed between snapshots.
+pub fn compute_usage(prev: &CpuTimes, curr: &CpuTimes) -> f64 {
+    let total_delta = curr.total().saturating_sub(prev.total());
+    if total_delta == 0 {
+        return 0.0;
+    }
+    let idle_delta = curr.idle_total().saturating_sub(prev.idle_total());
+    1.0 - (idle_delta as f64 / total_delta as f64)
+}
+
+/// A single hwmon `tempN_input` node, best-effort associated with a CPU ID
+/// when its chip/label naming makes that unambiguous.
+#[derive(Debug, Clone)]
+pub struct TempSensor {
+    pub label: String,
+    pub cpu_id: Option<usize>,
+    path: PathBuf,
+}
+
+/// `coretemp`/`k10temp` label per-core readings as `Core N`; match that to
+/// extract the CPU ID, otherwise report the sensor under its own label.
+fn cpu_id_from_label(chip_name: &str, label: &str) -> Option<usize> {
+    if chip_name != "coretemp" && chip_name != "k10temp" {
+        return None;
+    }
+    label.strip_prefix("Core ")?.trim().parse().ok()
+}
+
+/// Walk `/sys/class/hwmon/hwmon*/` and collect every `tempN_input` node
+/// found, labelling each from the sibling `tempN_label` file (or the chip's
+/// `name` file if no per-sensor label exists). Missing hwmon support (no
+/// `/sys/class/hwmon` at all) is not an error; it just yields no sensors.
+pub fn discover_hwmon_temps() -> Result<Vec<TempSensor>, ProbeError> {
+    let mut sensors = Vec::new();
+    let Ok(chips) = fs::read_dir(SYSFS_HWMON) else {
+        return Ok(sensors);
+    };
+    for chip in chips.filter_map(Result::ok) {
+        let dir = chip.path();
+        let chip_name = fs::read_to_string(dir.join("name"))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_else(|_| "hwmon".to_string());
+        let Ok(files) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for file in files.filter_map(Result::ok) {
+            let filename = file.file_name();
+            let filename = filename.to_string_lossy();
+            let Some(index) = filename
+                .strip_prefix("temp")
+                .and_then(|s| s.strip_suffix("_input"))
+            else {
                 continue;
             };
-            let line = line.trim_start();
-            let Some(line) = line.strip_prefix(":") else {
-                eprintln!("incorrrect file format");
-                exit(1)
-            };
-            let line = line.trim_start();
-            let id = line.parse::<usize>().expect("couldn't parse frequency");
-            if cpuset.contains(&id) {
-                current = Some(id);
-            }
+            let label = fs::read_to_string(dir.join(format!("temp{index}_label")))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_else(|_| format!("{chip_name} temp{index}"));
+            let cpu_id = cpu_id_from_label(&chip_name, &label);
+            sensors.push(TempSensor {
+                label,
+                cpu_id,
+                path: dir.join(filename.as_ref()),
+            });
         }
     }
-    Ok(cpu_frequencies)
+    Ok(sensors)
+}
+
+/// Read a sensor's current temperature in millidegrees Celsius.
+pub fn read_hwmon_temp(sensor: &TempSensor) -> Result<i64, ProbeError> {
+    read_sysfs_uint(&sensor.path).map(|v| v as i64)
 }